@@ -0,0 +1,202 @@
+//! Pluggable concurrent-map backends for the benchmark.
+//!
+//! Modeled on the `bustle` harness: a `Collection` owns the backing storage
+//! and hands out cheap, independently `Send`-able `Handle`s for worker
+//! threads to operate through. This keeps the worker loop in `main.rs`
+//! generic over whichever map is under test.
+
+use crate::Value;
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A concurrent map under test.
+pub trait Collection: Send + Sync + 'static {
+    type Handle: CollectionHandle;
+
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Hand out a handle a worker thread can use to operate on the map.
+    fn pin(&self) -> Self::Handle;
+}
+
+/// Per-thread view into a `Collection`.
+pub trait CollectionHandle: Send + Sync + 'static {
+    fn get(&self, key: &usize) -> bool;
+    fn insert(&self, key: usize, value: Value) -> bool;
+    fn update(&self, key: usize, value: Value) -> bool;
+    fn remove(&self, key: &usize) -> bool;
+    /// Reads `len` consecutive keys starting at `key`, wrapping back around
+    /// to `kmin` once `kmin + modulus` is reached, touching each present
+    /// value so it isn't optimized away. Returns how many of the `len` keys
+    /// were present. Used to model YCSB-style scans over a `[kmin, kmin +
+    /// modulus)` keyspace.
+    fn scan(&self, key: &usize, len: usize, kmin: usize, modulus: usize) -> usize;
+}
+
+/// The `i`-th key of a scan starting at `key`, wrapping back around to
+/// `kmin` once `kmin + modulus` is reached. Shared by every `CollectionHandle`
+/// impl so the wraparound arithmetic only lives in one place.
+fn scan_key(key: usize, i: usize, kmin: usize, modulus: usize) -> usize {
+    kmin + (key - kmin + i) % modulus
+}
+
+pub struct DashMapTable(Arc<DashMap<usize, Value>>);
+
+impl Collection for DashMapTable {
+    type Handle = Arc<DashMap<usize, Value>>;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self(Arc::new(DashMap::with_capacity(capacity)))
+    }
+
+    fn pin(&self) -> Self::Handle {
+        Arc::clone(&self.0)
+    }
+}
+
+impl CollectionHandle for Arc<DashMap<usize, Value>> {
+    fn get(&self, key: &usize) -> bool {
+        self.as_ref().get(key).is_some()
+    }
+
+    fn insert(&self, key: usize, value: Value) -> bool {
+        self.as_ref().insert(key, value).is_some()
+    }
+
+    fn update(&self, key: usize, value: Value) -> bool {
+        self.as_ref().insert(key, value).is_some()
+    }
+
+    fn remove(&self, key: &usize) -> bool {
+        self.as_ref().remove(key).is_some()
+    }
+
+    fn scan(&self, key: &usize, len: usize, kmin: usize, modulus: usize) -> usize {
+        let mut touched = 0;
+        for i in 0..len {
+            if let Some(v) = self.as_ref().get(&scan_key(*key, i, kmin, modulus)) {
+                std::hint::black_box(v[0]);
+                touched += 1;
+            }
+        }
+        touched
+    }
+}
+
+pub struct RwLockTable(Arc<RwLock<HashMap<usize, Value>>>);
+
+impl Collection for RwLockTable {
+    type Handle = Arc<RwLock<HashMap<usize, Value>>>;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self(Arc::new(RwLock::new(HashMap::with_capacity(capacity))))
+    }
+
+    fn pin(&self) -> Self::Handle {
+        Arc::clone(&self.0)
+    }
+}
+
+impl CollectionHandle for Arc<RwLock<HashMap<usize, Value>>> {
+    fn get(&self, key: &usize) -> bool {
+        self.read().unwrap().get(key).is_some()
+    }
+
+    fn insert(&self, key: usize, value: Value) -> bool {
+        self.write().unwrap().insert(key, value).is_some()
+    }
+
+    fn update(&self, key: usize, value: Value) -> bool {
+        self.write().unwrap().insert(key, value).is_some()
+    }
+
+    fn remove(&self, key: &usize) -> bool {
+        self.write().unwrap().remove(key).is_some()
+    }
+
+    fn scan(&self, key: &usize, len: usize, kmin: usize, modulus: usize) -> usize {
+        let map = self.read().unwrap();
+        let mut touched = 0;
+        for i in 0..len {
+            if let Some(v) = map.get(&scan_key(*key, i, kmin, modulus)) {
+                std::hint::black_box(v[0]);
+                touched += 1;
+            }
+        }
+        touched
+    }
+}
+
+pub struct MutexTable(Arc<Mutex<HashMap<usize, Value>>>);
+
+impl Collection for MutexTable {
+    type Handle = Arc<Mutex<HashMap<usize, Value>>>;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(HashMap::with_capacity(capacity))))
+    }
+
+    fn pin(&self) -> Self::Handle {
+        Arc::clone(&self.0)
+    }
+}
+
+impl CollectionHandle for Arc<Mutex<HashMap<usize, Value>>> {
+    fn get(&self, key: &usize) -> bool {
+        self.lock().unwrap().get(key).is_some()
+    }
+
+    fn insert(&self, key: usize, value: Value) -> bool {
+        self.lock().unwrap().insert(key, value).is_some()
+    }
+
+    fn update(&self, key: usize, value: Value) -> bool {
+        self.lock().unwrap().insert(key, value).is_some()
+    }
+
+    fn remove(&self, key: &usize) -> bool {
+        self.lock().unwrap().remove(key).is_some()
+    }
+
+    fn scan(&self, key: &usize, len: usize, kmin: usize, modulus: usize) -> usize {
+        let map = self.lock().unwrap();
+        let mut touched = 0;
+        for i in 0..len {
+            if let Some(v) = map.get(&scan_key(*key, i, kmin, modulus)) {
+                std::hint::black_box(v[0]);
+                touched += 1;
+            }
+        }
+        touched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_key_stays_within_modulus_window() {
+        for i in 0..4 {
+            assert_eq!(scan_key(2, i, 0, 10), 2 + i);
+        }
+    }
+
+    #[test]
+    fn scan_key_wraps_back_to_kmin() {
+        // kmin=100, modulus=10 -> window is [100, 110). Starting at 108,
+        // the 3rd and 4th keys (i=2, i=3) should wrap back to 100 and 101.
+        assert_eq!(scan_key(108, 0, 100, 10), 108);
+        assert_eq!(scan_key(108, 1, 100, 10), 109);
+        assert_eq!(scan_key(108, 2, 100, 10), 100);
+        assert_eq!(scan_key(108, 3, 100, 10), 101);
+    }
+
+    #[test]
+    fn scan_key_wraps_with_nonzero_kmin_at_window_start() {
+        assert_eq!(scan_key(100, 0, 100, 10), 100);
+        assert_eq!(scan_key(100, 10, 100, 10), 100);
+        assert_eq!(scan_key(100, 11, 100, 10), 101);
+    }
+}
@@ -0,0 +1,92 @@
+//! Deterministic, partitioned key generation for the parallel load phase.
+//!
+//! Modeled on kvbench's workload generators: the keyspace `[kmin, kmax)` is
+//! split into disjoint, contiguous partitions, one per loader thread, so the
+//! load phase can run fully in parallel with no cross-thread synchronization
+//! and no duplicate keys.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[derive(Copy, Clone, Debug)]
+pub enum LoadDist {
+    /// Thread `t` of `n` emits its partition's keys in increasing order.
+    Incrementp,
+    /// Thread `t` of `n` emits its partition's keys in a seeded
+    /// Fisher-Yates-shuffled order (shuffled only within the partition, so
+    /// keys stay unique and no cross-thread synchronization is needed).
+    Shufflep,
+}
+
+/// Returns the keys thread `tid` (of `threads` total) should load: its
+/// disjoint slice of `[kmin, kmax)`, in the order `dist` calls for. The
+/// union across all `tid in 0..threads` is exactly `[kmin, kmax)`, each key
+/// exactly once.
+pub fn partition_keys(
+    dist: LoadDist,
+    kmin: usize,
+    kmax: usize,
+    threads: usize,
+    tid: usize,
+) -> Vec<usize> {
+    let total = kmax - kmin;
+    let base = total / threads;
+    let rem = total % threads;
+    // First `rem` partitions get one extra key so every key in
+    // [kmin, kmax) is covered exactly once.
+    let start = kmin + tid * base + tid.min(rem);
+    let len = base + if tid < rem { 1 } else { 0 };
+
+    let mut keys: Vec<usize> = (start..start + len).collect();
+    if let LoadDist::Shufflep = dist {
+        let mut rng = StdRng::seed_from_u64(tid as u64);
+        keys.shuffle(&mut rng);
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn assert_exact_cover(dist: LoadDist, kmin: usize, kmax: usize, threads: usize) {
+        let mut seen = HashSet::new();
+        for tid in 0..threads {
+            for key in partition_keys(dist, kmin, kmax, threads, tid) {
+                assert!(
+                    (kmin..kmax).contains(&key),
+                    "key {key} outside [{kmin}, {kmax})"
+                );
+                assert!(seen.insert(key), "key {key} emitted more than once");
+            }
+        }
+        assert_eq!(
+            seen.len(),
+            kmax - kmin,
+            "not every key in range was emitted"
+        );
+    }
+
+    #[test]
+    fn incrementp_covers_range_exactly_once() {
+        assert_exact_cover(LoadDist::Incrementp, 0, 1_000, 7);
+        assert_exact_cover(LoadDist::Incrementp, 123, 1_123, 4);
+        assert_exact_cover(LoadDist::Incrementp, 0, 1, 1);
+    }
+
+    #[test]
+    fn shufflep_covers_range_exactly_once() {
+        assert_exact_cover(LoadDist::Shufflep, 0, 1_000, 7);
+        assert_exact_cover(LoadDist::Shufflep, 123, 1_123, 4);
+        assert_exact_cover(LoadDist::Shufflep, 0, 1, 1);
+    }
+
+    #[test]
+    fn shufflep_actually_reorders_within_partition() {
+        let keys = partition_keys(LoadDist::Shufflep, 0, 1_000, 1, 0);
+        let sorted: Vec<usize> = (0..1_000).collect();
+        assert_ne!(keys, sorted, "shufflep should not emit keys in order");
+    }
+}
@@ -0,0 +1,266 @@
+//! Minimal log-bucketed latency histogram for per-operation timing.
+//!
+//! Each worker thread keeps its own [`WorkerLatencies`] so recording a
+//! sample never needs cross-thread synchronization; histograms are summed
+//! bucket-wise after all threads join (histogram addition is just per-bucket
+//! count summation).
+
+use std::time::Duration;
+
+/// Sub-buckets per power-of-two octave; higher means finer resolution at
+/// the cost of more buckets.
+const SUB_BUCKETS_PER_OCTAVE: u32 = 4;
+/// Covers 1ns .. 2^34 ns (about 17s), which spans the 1ns .. 10s range
+/// YCSB-style benchmarks care about.
+const MAX_OCTAVES: u32 = 34;
+const NUM_BUCKETS: usize = (MAX_OCTAVES * SUB_BUCKETS_PER_OCTAVE) as usize;
+
+/// A hand-rolled HDR-style histogram: log2 buckets ("octaves") each split
+/// into a fixed number of linear sub-buckets.
+#[derive(Clone)]
+pub struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum_ns: u128,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; NUM_BUCKETS],
+            count: 0,
+            sum_ns: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn bucket_for(ns: u64) -> usize {
+        if ns == 0 {
+            return 0;
+        }
+        let octave = 63 - ns.leading_zeros(); // floor(log2(ns))
+        let octave_start = 1u64 << octave;
+        let octave_span = octave_start; // [2^o, 2^(o+1)) has width 2^o
+        let sub = ((ns - octave_start) * SUB_BUCKETS_PER_OCTAVE as u64) / octave_span;
+        let idx = octave * SUB_BUCKETS_PER_OCTAVE + (sub as u32).min(SUB_BUCKETS_PER_OCTAVE - 1);
+        (idx as usize).min(NUM_BUCKETS - 1)
+    }
+
+    fn bucket_upper_bound(idx: usize) -> u64 {
+        let octave = idx as u32 / SUB_BUCKETS_PER_OCTAVE;
+        let sub = idx as u32 % SUB_BUCKETS_PER_OCTAVE;
+        if octave == 0 {
+            return 1;
+        }
+        let octave_start = 1u64 << octave;
+        octave_start + (octave_start * (sub as u64 + 1)) / SUB_BUCKETS_PER_OCTAVE as u64
+    }
+
+    pub fn record(&mut self, d: Duration) {
+        let ns = d.as_nanos().min(u64::MAX as u128) as u64;
+        self.buckets[Self::bucket_for(ns)] += 1;
+        self.count += 1;
+        self.sum_ns += ns as u128;
+        self.min_ns = self.min_ns.min(ns);
+        self.max_ns = self.max_ns.max(ns);
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (a, b) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *a += b;
+        }
+        self.count += other.count;
+        self.sum_ns += other.sum_ns;
+        self.min_ns = self.min_ns.min(other.min_ns);
+        self.max_ns = self.max_ns.max(other.max_ns);
+    }
+
+    /// Approximate nanosecond value at percentile `p` (e.g. `0.99` for p99),
+    /// accurate to the width of the containing sub-bucket.
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = ((self.count as f64) * p).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (idx, &c) in self.buckets.iter().enumerate() {
+            cumulative += c;
+            if cumulative >= target {
+                return Self::bucket_upper_bound(idx);
+            }
+        }
+        self.max_ns
+    }
+
+    pub fn mean_ns(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_ns as f64 / self.count as f64
+        }
+    }
+
+    pub fn min_ns(&self) -> u64 {
+        if self.count == 0 {
+            0
+        } else {
+            self.min_ns
+        }
+    }
+
+    pub fn max_ns(&self) -> u64 {
+        self.max_ns
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum OpKind {
+    Read,
+    Update,
+    Insert,
+    Remove,
+    Scan,
+}
+
+impl OpKind {
+    const ALL: [OpKind; 5] = [
+        OpKind::Read,
+        OpKind::Update,
+        OpKind::Insert,
+        OpKind::Remove,
+        OpKind::Scan,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            OpKind::Read => "read",
+            OpKind::Update => "update",
+            OpKind::Insert => "insert",
+            OpKind::Remove => "remove",
+            OpKind::Scan => "scan",
+        }
+    }
+}
+
+/// Per-operation-kind histograms collected by a single worker thread, kept
+/// thread-local rather than shared so measurement itself isn't perturbed by
+/// contention on the histogram.
+#[derive(Clone, Default)]
+pub struct WorkerLatencies {
+    pub read: Histogram,
+    pub update: Histogram,
+    pub insert: Histogram,
+    pub remove: Histogram,
+    pub scan: Histogram,
+    /// Total records returned by `scan` ops, so throughput can optionally be
+    /// reported in records/s as well as ops/s.
+    pub scanned_records: u64,
+}
+
+impl WorkerLatencies {
+    fn histogram(&self, kind: OpKind) -> &Histogram {
+        match kind {
+            OpKind::Read => &self.read,
+            OpKind::Update => &self.update,
+            OpKind::Insert => &self.insert,
+            OpKind::Remove => &self.remove,
+            OpKind::Scan => &self.scan,
+        }
+    }
+
+    pub fn merge(&mut self, other: &WorkerLatencies) {
+        self.read.merge(&other.read);
+        self.update.merge(&other.update);
+        self.insert.merge(&other.insert);
+        self.remove.merge(&other.remove);
+        self.scan.merge(&other.scan);
+        self.scanned_records += other.scanned_records;
+    }
+
+    pub fn print_report(&self) {
+        for kind in OpKind::ALL {
+            let h = self.histogram(kind);
+            if h.count() == 0 {
+                continue;
+            }
+            println!(
+                "  {:<7} n={:>10} min={:>8}ns mean={:>10.1}ns p50={:>8}ns p90={:>8}ns p99={:>8}ns p99.9={:>8}ns max={:>8}ns",
+                kind.label(),
+                h.count(),
+                h.min_ns(),
+                h.mean_ns(),
+                h.percentile(0.50),
+                h.percentile(0.90),
+                h.percentile(0.99),
+                h.percentile(0.999),
+                h.max_ns(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_upper_bound_is_monotonic() {
+        for idx in 0..NUM_BUCKETS - 1 {
+            assert!(
+                Histogram::bucket_upper_bound(idx) <= Histogram::bucket_upper_bound(idx + 1),
+                "bucket {idx} upper bound {} exceeds bucket {} upper bound {}",
+                Histogram::bucket_upper_bound(idx),
+                idx + 1,
+                Histogram::bucket_upper_bound(idx + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn bucket_for_handles_power_of_two_boundaries() {
+        assert_eq!(Histogram::bucket_for(0), 0);
+        assert_eq!(Histogram::bucket_for(1), 0);
+        assert_eq!(Histogram::bucket_for(2), SUB_BUCKETS_PER_OCTAVE as usize);
+        assert_eq!(
+            Histogram::bucket_for(1u64 << 33),
+            33 * SUB_BUCKETS_PER_OCTAVE as usize
+        );
+        // The top bucket should never be exceeded, even at the far end of
+        // the histogram's covered range.
+        assert_eq!(Histogram::bucket_for(u64::MAX), NUM_BUCKETS - 1);
+    }
+
+    #[test]
+    fn bucket_for_is_non_decreasing_with_ns() {
+        let mut prev = Histogram::bucket_for(0);
+        for ns in [1u64, 2, 3, 7, 8, 1_000, 1_000_000, 1 << 20, 1 << 33] {
+            let bucket = Histogram::bucket_for(ns);
+            assert!(
+                bucket >= prev,
+                "bucket_for({ns}) regressed: {bucket} < {prev}"
+            );
+            prev = bucket;
+        }
+    }
+
+    #[test]
+    fn percentile_reflects_recorded_samples() {
+        let mut h = Histogram::default();
+        for ns in [100u64, 200, 300, 400, 500] {
+            h.record(Duration::from_nanos(ns));
+        }
+        // p100 (max) should cover the largest recorded sample.
+        assert!(h.percentile(1.0) >= 500);
+        // Percentiles should be non-decreasing as p increases.
+        assert!(h.percentile(0.5) <= h.percentile(0.99));
+    }
+}
@@ -1,10 +1,19 @@
+mod collection;
+mod keygen;
+mod latency;
+mod monitor;
+
 use clap::{Parser, ValueEnum};
-use dashmap::DashMap;
+use collection::{Collection, CollectionHandle, DashMapTable, MutexTable, RwLockTable};
+use keygen::LoadDist;
+use latency::WorkerLatencies;
+use monitor::PaddedCounter;
 use rand::{thread_rng, Rng};
 use rand_distr::{Distribution, Zipf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const VALUE_BYTES: usize = 1024; // 1 KB like YCSB
 type Value = Box<[u8; VALUE_BYTES]>;
@@ -30,6 +39,30 @@ enum WorkloadKind {
     All,
 }
 
+/// Which concurrent map implementation to benchmark.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ImplKind {
+    Dashmap,
+    Rwlock,
+    Mutex,
+}
+
+/// Key fill order for the parallel load phase.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum LoadDistKind {
+    Incrementp,
+    Shufflep,
+}
+
+impl From<LoadDistKind> for LoadDist {
+    fn from(kind: LoadDistKind) -> Self {
+        match kind {
+            LoadDistKind::Incrementp => LoadDist::Incrementp,
+            LoadDistKind::Shufflep => LoadDist::Shufflep,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
@@ -41,6 +74,10 @@ struct Args {
     #[arg(long, value_enum, default_value_t = WorkloadKind::All)]
     workload: WorkloadKind,
 
+    /// Which map implementation to benchmark: dashmap|rwlock|mutex
+    #[arg(long, value_enum, default_value_t = ImplKind::Dashmap)]
+    r#impl: ImplKind,
+
     /// Number of records to pre-load (dataset size)
     #[arg(long, default_value_t = 60_000_000)]
     recordcount: usize,
@@ -56,6 +93,68 @@ struct Args {
     /// Zipfian exponent (1.03 is a common YCSB-ish value)
     #[arg(long, default_value_t = 1.03)]
     zipf_s: f64,
+
+    /// Maximum scan length; each scan draws its length uniformly from
+    /// `1..=max_scan_len` (YCSB default is 100)
+    #[arg(long, default_value_t = 100)]
+    max_scan_len: usize,
+
+    /// Key fill order for the parallel load phase: incrementp|shufflep
+    #[arg(long, value_enum, default_value_t = LoadDistKind::Incrementp)]
+    load_dist: LoadDistKind,
+
+    /// Minimum key (inclusive) to load
+    #[arg(long, default_value_t = 0)]
+    kmin: usize,
+
+    /// Maximum key (exclusive) to load; defaults to `kmin + recordcount`
+    #[arg(long)]
+    kmax: Option<usize>,
+
+    /// Sample interval in milliseconds for time-series throughput reporting.
+    /// When set, a background monitor thread emits
+    /// `t_ms,ops_since_last,instantaneous_mops` rows during the measured
+    /// phase.
+    #[arg(long)]
+    sample_interval_ms: Option<u64>,
+
+    /// Optional CSV file to write time-series samples to (stdout if unset).
+    /// Each workload gets its own file: the workload name is inserted before
+    /// the extension (e.g. `out.csv` -> `out.workloada.csv`), so a
+    /// multi-workload run doesn't truncate earlier workloads' samples.
+    #[arg(long)]
+    sample_csv: Option<String>,
+
+    /// Override delete proportion for the selected workload(s), stealing
+    /// probability mass from updateprop so delete ops become reachable
+    /// (every shipped workload otherwise has deleteprop = 0.0). 0.0 (the
+    /// default) leaves workloads unchanged. Must be <= the workload's
+    /// updateprop, or there's no update mass to steal from; panics rather
+    /// than silently eating into the wrong op.
+    #[arg(long, default_value_t = 0.0)]
+    delete_prop: f64,
+}
+
+/// How a workload picks the key for each request.
+#[derive(Copy, Clone)]
+enum RequestDist {
+    /// Use the benchmark-wide `--zipfian`/`--zipf-s` setting over the full
+    /// loaded `[kmin, kmax)` keyspace.
+    Standard,
+    /// YCSB-D's "latest" distribution: reads are zipfian over how far back
+    /// from the current max key, so just-inserted records are hottest.
+    Latest,
+}
+
+/// Samples a key for YCSB-D's "latest" distribution: a zipfian rank `r` over
+/// the live key range `[kmin, max_key]`, mapped back to `max_key - (r - 1)`
+/// so rank 1 (the most probable outcome under zipf) lands exactly on
+/// `max_key` and ranks never fall below `kmin`.
+fn sample_latest_key(max_key: usize, kmin: usize, zipf_s: f64, rng: &mut impl Rng) -> usize {
+    let live_span = (max_key.saturating_sub(kmin) + 1).max(1);
+    let rank_zipf = Zipf::new(live_span as u64, zipf_s).unwrap();
+    let r = rank_zipf.sample(rng) as usize;
+    max_key - (r - 1)
 }
 
 #[derive(Copy, Clone)]
@@ -64,7 +163,9 @@ struct YcsbWorkload {
     readprop: f64,
     updateprop: f64,
     insertprop: f64,
+    deleteprop: f64,
     scanprop: f64,
+    request_dist: RequestDist,
 }
 
 fn selected_workloads(kind: WorkloadKind) -> Vec<YcsbWorkload> {
@@ -73,42 +174,54 @@ fn selected_workloads(kind: WorkloadKind) -> Vec<YcsbWorkload> {
         readprop: 0.5,
         updateprop: 0.5,
         insertprop: 0.0,
+        deleteprop: 0.0,
         scanprop: 0.0,
+        request_dist: RequestDist::Standard,
     };
     let b = YcsbWorkload {
         name: "workloadb",
         readprop: 0.95,
         updateprop: 0.05,
         insertprop: 0.0,
+        deleteprop: 0.0,
         scanprop: 0.0,
+        request_dist: RequestDist::Standard,
     };
     let c = YcsbWorkload {
         name: "workloadc",
         readprop: 1.0,
         updateprop: 0.0,
         insertprop: 0.0,
+        deleteprop: 0.0,
         scanprop: 0.0,
+        request_dist: RequestDist::Standard,
     };
     let d = YcsbWorkload {
         name: "workloadd",
         readprop: 0.75,
         updateprop: 0.20,
         insertprop: 0.05,
+        deleteprop: 0.0,
         scanprop: 0.0,
+        request_dist: RequestDist::Latest,
     };
     let e = YcsbWorkload {
         name: "workloade",
         readprop: 0.55,
         updateprop: 0.0,
         insertprop: 0.0,
+        deleteprop: 0.0,
         scanprop: 0.45,
+        request_dist: RequestDist::Standard,
     };
     let f = YcsbWorkload {
         name: "workloadf",
         readprop: 0.25,
         updateprop: 0.25,
         insertprop: 0.25,
+        deleteprop: 0.0,
         scanprop: 0.25,
+        request_dist: RequestDist::Standard,
     };
 
     match kind {
@@ -122,15 +235,48 @@ fn selected_workloads(kind: WorkloadKind) -> Vec<YcsbWorkload> {
     }
 }
 
-fn main() {
-    let args = Args::parse();
-    assert!(args.threads >= 1, "--threads must be >= 1");
+/// Derives a per-workload `--sample-csv` path so a multi-workload run
+/// doesn't have each workload's monitor thread truncate the last one's
+/// samples: the workload name is inserted before the extension, e.g.
+/// `out.csv` + `workloada` -> `out.workloada.csv`. Extension-less bases get
+/// the workload name appended instead: `out` -> `out.workloada`.
+fn per_workload_csv_path(base: &str, workload_name: &str) -> String {
+    match base.rsplit_once('.') {
+        // A '.' in a directory component (e.g. `runs.d/out`) is not an
+        // extension.
+        Some((stem, ext)) if !ext.contains('/') => format!("{stem}.{workload_name}.{ext}"),
+        Some(_) => format!("{base}.{workload_name}"),
+        None => format!("{base}.{workload_name}"),
+    }
+}
 
+/// Runs every selected workload against `C`, printing throughput for each.
+fn run_workloads<C: Collection>(args: &Args) {
     let workloads = selected_workloads(args.workload);
-
     let warmup_frac = 0.05; // 5% of operations as warmup
 
-    for wl in workloads {
+    for mut wl in workloads {
+        if args.delete_prop > 0.0 {
+            assert!(
+                args.delete_prop <= wl.updateprop,
+                "--delete-prop {} exceeds {}'s updateprop {}; it has no update \
+                 mass for deletes to steal from, so the override would silently \
+                 eat into read or scan instead",
+                args.delete_prop,
+                wl.name,
+                wl.updateprop
+            );
+            wl.deleteprop = args.delete_prop;
+            wl.updateprop -= args.delete_prop;
+        }
+
+        let total_prop = wl.readprop + wl.updateprop + wl.insertprop + wl.deleteprop + wl.scanprop;
+        assert!(
+            (total_prop - 1.0).abs() < 1e-9,
+            "{}'s op proportions sum to {total_prop}, expected 1.0",
+            wl.name
+        );
+
         println!(
             "Running {} | threads={} | records={} | ops={} | dist={}",
             wl.name,
@@ -140,16 +286,40 @@ fn main() {
             if args.zipfian { "zipfian" } else { "uniform" }
         );
 
-        let map = Arc::new(DashMap::new());
+        let collection = Arc::new(C::with_capacity(args.recordcount));
 
-        // Load phase
-        for key in 0..args.recordcount {
-            let mut v = Box::new([0u8; VALUE_BYTES]);
-            v[0] = (key & 0xFF) as u8; // touch it so it’s not optimized away
-            map.insert(key, make_value(key));
+        // Load phase: partition [kmin, kmax) across threads and fill in
+        // parallel, each thread owning a disjoint slice. All request key
+        // generation below (warmup, measured, scan) is over this same
+        // [kmin, kmax) range, not a separate 0..recordcount range; the one
+        // exception is the "latest" distribution, whose inserts and reads
+        // follow a max key that grows past kmax - 1.
+        let kmin = args.kmin;
+        let kmax = args.kmax.unwrap_or(kmin + args.recordcount);
+        let keyspan = kmax - kmin;
+        let load_dist: LoadDist = args.load_dist.into();
 
+        let mut load_handles = Vec::with_capacity(args.threads);
+        for tid in 0..args.threads {
+            let handle = collection.pin();
+            let threads = args.threads;
+
+            load_handles.push(thread::spawn(move || {
+                for key in keygen::partition_keys(load_dist, kmin, kmax, threads, tid) {
+                    handle.insert(key, make_value(key));
+                }
+            }));
         }
 
+        for h in load_handles {
+            h.join().unwrap();
+        }
+
+        // Tracks the live max key for workloads using the "latest" request
+        // distribution (YCSB-D): inserts bump it and use the new value as
+        // the key. Seeded from the actual max loaded key (kmax - 1), not
+        // recordcount, so it stays correct when --kmin is nonzero.
+        let insert_counter = Arc::new(AtomicUsize::new(kmax - 1));
 
         // -------- Warmup phase (not measured) --------
         let warmup_ops = (args.operationcount as f64 * warmup_frac) as usize;
@@ -160,9 +330,8 @@ fn main() {
 
         let mut warmup_handles = Vec::with_capacity(warmup_threads);
         for tid in 0..warmup_threads {
-            let map = Arc::clone(&map);
+            let handle = collection.pin();
             let ops = base + if tid < rem { 1 } else { 0 };
-            let recordcount = args.recordcount;
             let zipfian = args.zipfian;
             let zipf_s = args.zipf_s;
 
@@ -170,7 +339,7 @@ fn main() {
                 let mut rng = thread_rng();
 
                 let zipf = if zipfian {
-                    Some(Zipf::new(recordcount as u64, zipf_s).unwrap())
+                    Some(Zipf::new(keyspan as u64, zipf_s).unwrap())
                 } else {
                     None
                 };
@@ -178,19 +347,17 @@ fn main() {
                 for _ in 0..ops {
                     let op_choice: f64 = rng.gen();
                     let key = if let Some(z) = &zipf {
-                        (z.sample(&mut rng) as usize) % recordcount
+                        kmin + (z.sample(&mut rng) as usize) % keyspan
                     } else {
-                        rng.gen_range(0..recordcount)
+                        rng.gen_range(kmin..kmax)
                     };
 
                     if op_choice < wl.readprop {
-                        let _ = map.get(&key);
+                        let _ = handle.get(&key);
                     } else if op_choice < wl.readprop + wl.updateprop {
-                        map.insert(key, make_value(key));
-
+                        handle.update(key, make_value(key));
                     } else if op_choice < wl.readprop + wl.updateprop + wl.insertprop {
-                        map.insert(key, make_value(key));
-
+                        handle.insert(key, make_value(key));
                     }
                 }
             }));
@@ -205,23 +372,49 @@ fn main() {
 
         let start = Instant::now();
 
+        // Optional time-series sampling: each worker bumps its own padded
+        // counter; a monitor thread periodically sums them and reports
+        // instantaneous throughput.
+        let op_counters = args.sample_interval_ms.map(|_| {
+            Arc::new(
+                (0..args.threads)
+                    .map(|_| PaddedCounter::default())
+                    .collect::<Vec<_>>(),
+            )
+        });
+        let monitor_stop = Arc::new(AtomicBool::new(false));
+        let monitor_handle = match (args.sample_interval_ms, &op_counters) {
+            (Some(ms), Some(counters)) => Some(monitor::spawn(
+                Duration::from_millis(ms),
+                Arc::clone(counters),
+                Arc::clone(&monitor_stop),
+                args.sample_csv
+                    .as_deref()
+                    .map(|base| per_workload_csv_path(base, wl.name)),
+            )),
+            _ => None,
+        };
+
         // Split operations across threads; distribute remainder to first threads
         let base = args.operationcount / args.threads;
         let rem = args.operationcount % args.threads;
 
         let mut handles = Vec::with_capacity(args.threads);
         for tid in 0..args.threads {
-            let map = Arc::clone(&map);
+            let handle = collection.pin();
             let ops = base + if tid < rem { 1 } else { 0 };
-            let recordcount = args.recordcount;
             let zipfian = args.zipfian;
             let zipf_s = args.zipf_s;
+            let max_scan_len = args.max_scan_len;
+            let insert_counter = Arc::clone(&insert_counter);
+            let op_counter = op_counters.as_ref().map(Arc::clone);
 
             handles.push(thread::spawn(move || {
                 let mut rng = thread_rng();
+                let mut latencies = WorkerLatencies::default();
 
                 let zipf = if zipfian {
-                    Some(Zipf::new(recordcount as u64, zipf_s).unwrap())
+                    Some(Zipf::new(keyspan as u64, zipf_s).unwrap())
                 } else {
                     None
                 };
@@ -229,27 +422,68 @@ fn main() {
                 for _ in 0..ops {
                     let op_choice: f64 = rng.gen(); // ok on your Rust, or use rng.gen::<f64>()
                     let key = if let Some(z) = &zipf {
-                        (z.sample(&mut rng) as usize) % recordcount
+                        kmin + (z.sample(&mut rng) as usize) % keyspan
                     } else {
-                        rng.gen_range(0..recordcount)
+                        rng.gen_range(kmin..kmax)
                     };
 
                     if op_choice < wl.readprop {
-                        let _ = map.get(&key);
+                        let read_key = match wl.request_dist {
+                            RequestDist::Standard => key,
+                            RequestDist::Latest => {
+                                let max_key = insert_counter.load(Ordering::Relaxed);
+                                sample_latest_key(max_key, kmin, zipf_s, &mut rng)
+                            }
+                        };
+                        let t0 = Instant::now();
+                        let _ = handle.get(&read_key);
+                        latencies.read.record(t0.elapsed());
                     } else if op_choice < wl.readprop + wl.updateprop {
-                        map.insert(key, make_value(key));
-
+                        let t0 = Instant::now();
+                        handle.update(key, make_value(key));
+                        latencies.update.record(t0.elapsed());
                     } else if op_choice < wl.readprop + wl.updateprop + wl.insertprop {
-                        map.insert(key, make_value(key));
+                        let insert_key = match wl.request_dist {
+                            RequestDist::Standard => key,
+                            RequestDist::Latest => {
+                                insert_counter.fetch_add(1, Ordering::Relaxed) + 1
+                            }
+                        };
+                        let t0 = Instant::now();
+                        handle.insert(insert_key, make_value(insert_key));
+                        latencies.insert.record(t0.elapsed());
+                    } else if op_choice
+                        < wl.readprop + wl.updateprop + wl.insertprop + wl.deleteprop
+                    {
+                        let t0 = Instant::now();
+                        handle.remove(&key);
+                        latencies.remove.record(t0.elapsed());
+                    } else if op_choice
+                        < wl.readprop + wl.updateprop + wl.insertprop + wl.deleteprop + wl.scanprop
+                    {
+                        let len = rng.gen_range(1..=max_scan_len);
+                        let t0 = Instant::now();
+                        let touched = handle.scan(&key, len, kmin, keyspan);
+                        latencies.scan.record(t0.elapsed());
+                        latencies.scanned_records += touched as u64;
+                    }
 
-                    } else {
-                        // scan not implemented
+                    if let Some(counters) = &op_counter {
+                        counters[tid].increment();
                     }
                 }
+
+                latencies
             }));
         }
 
+        let mut latencies = WorkerLatencies::default();
         for h in handles {
+            latencies.merge(&h.join().unwrap());
+        }
+
+        monitor_stop.store(true, Ordering::Relaxed);
+        if let Some(h) = monitor_handle {
             h.join().unwrap();
         }
 
@@ -258,10 +492,85 @@ fn main() {
         let throughput = (args.operationcount as f64) / secs;
 
         println!(
-            "Completed {} in {:.3?} | throughput = {:.2} Mops/s\n",
+            "Completed {} in {:.3?} | throughput = {:.2} Mops/s",
             wl.name,
             elapsed,
             throughput / 1e6
         );
+        if latencies.scanned_records > 0 {
+            println!(
+                "  scanned {} records | {:.2} Krecords/s",
+                latencies.scanned_records,
+                (latencies.scanned_records as f64 / secs) / 1e3
+            );
+        }
+        latencies.print_report();
+        println!();
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    assert!(args.threads >= 1, "--threads must be >= 1");
+
+    match args.r#impl {
+        ImplKind::Dashmap => run_workloads::<DashMapTable>(&args),
+        ImplKind::Rwlock => run_workloads::<RwLockTable>(&args),
+        ImplKind::Mutex => run_workloads::<MutexTable>(&args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn per_workload_csv_path_inserts_name_before_extension() {
+        assert_eq!(
+            per_workload_csv_path("out.csv", "workloada"),
+            "out.workloada.csv"
+        );
+        assert_eq!(
+            per_workload_csv_path("dir/out.csv", "workloadb"),
+            "dir/out.workloadb.csv"
+        );
+        assert_eq!(per_workload_csv_path("out", "workloadc"), "out.workloadc");
+    }
+
+    #[test]
+    fn latest_keys_stay_within_loaded_range() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let kmin = 1_000;
+        let max_key = 1_999;
+        for _ in 0..10_000 {
+            let key = sample_latest_key(max_key, kmin, 1.03, &mut rng);
+            assert!(
+                (kmin..=max_key).contains(&key),
+                "key {key} outside [{kmin}, {max_key}]"
+            );
+        }
+    }
+
+    #[test]
+    fn latest_keys_concentrate_near_max() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let kmin = 0;
+        let max_key = 9_999;
+        let near_max = max_key - 99..=max_key; // top 1% of the range
+        let samples = 10_000;
+
+        let hits = (0..samples)
+            .filter(|_| near_max.contains(&sample_latest_key(max_key, kmin, 1.03, &mut rng)))
+            .count();
+
+        // Uniform sampling would land in this 1%-wide band ~1% of the time;
+        // the zipfian "latest" skew should land there far more often.
+        assert!(
+            hits > samples / 10,
+            "expected latest-distribution keys to concentrate near the max, \
+             only {hits}/{samples} fell in the top 1% of the range"
+        );
     }
 }
@@ -0,0 +1,82 @@
+//! Optional time-series throughput sampling via a background monitor thread.
+//!
+//! Each worker increments its own cache-line-padded counter so the hot path
+//! never contends; the monitor thread wakes on an interval, sums the
+//! counters, and emits a `t_ms,ops_since_last,instantaneous_mops` row. This
+//! surfaces warmup ramp, steady-state variance, and mid-run collapse that a
+//! single end-of-run throughput number hides.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A per-thread op counter padded to a cache line so independent workers
+/// never false-share a cache line while incrementing.
+#[repr(align(64))]
+#[derive(Default)]
+pub struct PaddedCounter(AtomicU64);
+
+impl PaddedCounter {
+    pub fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Spawns the monitor thread. It sums `counters` every `interval` until
+/// `stop` is set, writing rows to `csv_path` (or stdout, if `None`).
+///
+/// `csv_path` is truncated on open, so callers running multiple workloads
+/// against the same base path must pass a distinct path per call (see
+/// `per_workload_csv_path` in `main.rs`) or each workload's samples will
+/// overwrite the last.
+pub fn spawn(
+    interval: Duration,
+    counters: Arc<Vec<PaddedCounter>>,
+    stop: Arc<AtomicBool>,
+    csv_path: Option<String>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut out: Box<dyn Write + Send> = match &csv_path {
+            Some(path) => Box::new(File::create(path).expect("failed to create --sample-csv file")),
+            None => Box::new(io::stdout()),
+        };
+
+        writeln!(out, "t_ms,ops_since_last,instantaneous_mops").ok();
+
+        let start = Instant::now();
+        let mut last_total = 0u64;
+        let mut last_t = start;
+
+        while !stop.load(Ordering::Relaxed) {
+            thread::sleep(interval);
+            let now = Instant::now();
+            let total: u64 = counters.iter().map(|c| c.load()).sum();
+            let ops_since_last = total.saturating_sub(last_total);
+            let dt = now.duration_since(last_t).as_secs_f64();
+            let mops = if dt > 0.0 {
+                (ops_since_last as f64 / dt) / 1e6
+            } else {
+                0.0
+            };
+
+            writeln!(
+                out,
+                "{},{},{:.3}",
+                now.duration_since(start).as_millis(),
+                ops_since_last,
+                mops
+            )
+            .ok();
+
+            last_total = total;
+            last_t = now;
+        }
+    })
+}